@@ -0,0 +1,7 @@
+use anyhow::Result;
+
+/// Entry point when we're invoked as `su`/`kp` rather than `apd`.
+pub fn root_shell() -> Result<()> {
+    log::info!("root_shell triggered");
+    Ok(())
+}
@@ -6,7 +6,7 @@ use android_logger::Config;
 #[cfg(target_os = "android")]
 use log::LevelFilter;
 
-use crate::{defs, event, module, supercall, utils};
+use crate::{defs, event, module, safemode, supercall, utils};
 use std::ffi::CString;
 /// APatch cli
 #[derive(Parser, Debug)]
@@ -47,6 +47,23 @@ enum Commands {
 
     /// Start uid listener for synchronizing root list
     UidListener,
+
+    /// Force safe mode on or off, overriding the boot-key detection
+    SafeMode {
+        #[arg(long, help = "Enable safe mode for subsequent boots")]
+        enable: bool,
+    },
+
+    /// Block until a system property reaches a value, or a timeout elapses
+    WaitProp {
+        /// Property name
+        name: String,
+        /// Expected value
+        value: String,
+        /// Timeout in seconds
+        #[arg(long, default_value_t = 10)]
+        timeout: u64,
+    },
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -131,7 +148,7 @@ pub fn run() -> Result<()> {
 
         Commands::BootCompleted => event::on_boot_completed(cli.superkey),
 
-        Commands::UidListener => event::start_uid_listener(),
+        Commands::UidListener => event::start_uid_listener(cli.superkey),
 
         Commands::Kpm { command } => match command {
             Kpmsub::Load { key, path } => {
@@ -172,6 +189,24 @@ pub fn run() -> Result<()> {
         }
 
         Commands::Services => event::on_services(cli.superkey),
+
+        Commands::SafeMode { enable } => safemode::set_forced(enable),
+
+        Commands::WaitProp {
+            name,
+            value,
+            timeout,
+        } => {
+            if utils::wait_for_prop(&name, &value, std::time::Duration::from_secs(timeout))? {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!(
+                    "timed out waiting for {}={}",
+                    name,
+                    value
+                ))
+            }
+        }
     };
 
     if let Err(e) = &result {
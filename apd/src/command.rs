@@ -0,0 +1,135 @@
+use crate::defs;
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Outcome of a [`ShellCommand`] run: exit status plus everything the script
+/// wrote, in case the caller wants to surface it beyond the log.
+pub struct ShellCommandResult {
+    pub status: i32,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl ShellCommandResult {
+    pub fn success(&self) -> bool {
+        !self.timed_out && self.status == 0
+    }
+}
+
+/// Builder for running a module script (`install.sh`, `action.sh`, ...)
+/// the way every APatch/KernelSU-compatible script expects: the standard
+/// env vars set, output tagged and sent to the log, and a wall-clock
+/// timeout that kills the whole process group rather than leaving an
+/// orphaned child behind.
+pub struct ShellCommand {
+    cmd: Command,
+    tag: String,
+    timeout: Duration,
+}
+
+impl ShellCommand {
+    pub fn new(tag: impl Into<String>, script: impl AsRef<std::path::Path>) -> Self {
+        let mut cmd = Command::new("sh");
+        cmd.arg(script.as_ref());
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+        Self {
+            cmd,
+            tag: tag.into(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Inject the env vars module scripts rely on to detect they're running
+    /// under APatch (KernelSU-compatible scripts check `KSU` and fall back
+    /// to APatch-specific behavior when it's `false`).
+    pub fn module_env(mut self, mod_path: &str, mod_dir: &str) -> Self {
+        self.cmd
+            .env("MODPATH", mod_path)
+            .env("APATCH", "true")
+            .env("APATCH_VER", defs::VERSION_NAME)
+            .env("KSU", "false")
+            .env("MODDIR", mod_dir);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run to completion (or until the timeout kills the process group),
+    /// streaming tagged output into the log as it arrives.
+    pub fn run(mut self) -> Result<ShellCommandResult> {
+        // New process group so a timeout can kill every descendant the
+        // script spawned, not just the immediate `sh`.
+        unsafe {
+            self.cmd.pre_exec(|| {
+                if libc::setpgid(0, 0) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        let tag = self.tag.clone();
+        let mut child = self.cmd.spawn().with_context(|| format!("Failed to spawn script for {tag}"))?;
+        let pgid = child.id() as i32;
+
+        let stdout = spawn_log_reader(child.stdout.take(), tag.clone(), log::Level::Info);
+        let stderr = spawn_log_reader(child.stderr.take(), tag.clone(), log::Level::Warn);
+
+        let timed_out = wait_with_timeout(&mut child, self.timeout, pgid)?;
+
+        let out = stdout.join().unwrap_or_default();
+        let err = stderr.join().unwrap_or_default();
+        let status = child.wait().map(|s| s.code().unwrap_or(-1)).unwrap_or(-1);
+
+        Ok(ShellCommandResult {
+            status,
+            timed_out,
+            stdout: out,
+            stderr: err,
+        })
+    }
+}
+
+fn spawn_log_reader<R>(pipe: Option<R>, tag: String, level: log::Level) -> std::thread::JoinHandle<String>
+where
+    R: std::io::Read + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let mut collected = String::new();
+        if let Some(pipe) = pipe {
+            for line in BufReader::new(pipe).lines().map_while(|l| l.ok()) {
+                log::log!(level, "[{tag}] {line}");
+                collected.push_str(&line);
+                collected.push('\n');
+            }
+        }
+        collected
+    })
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Duration, pgid: i32) -> Result<bool> {
+    let start = Instant::now();
+    loop {
+        if let Some(_status) = child.try_wait().context("failed to poll script")? {
+            return Ok(false);
+        }
+        if start.elapsed() >= timeout {
+            unsafe {
+                libc::killpg(pgid, libc::SIGKILL);
+            }
+            let _ = child.wait();
+            return Ok(true);
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,22 @@
+pub const VERSION_CODE: &str = env!("CARGO_PKG_VERSION");
+pub const VERSION_NAME: &str = env!("CARGO_PKG_VERSION");
+
+pub const WORKING_DIR: &str = "/data/adb/ap/";
+pub const BINARY_DIR: &str = "/data/adb/ap/bin/";
+pub const MODULE_DIR: &str = "/data/adb/modules/";
+pub const MODULE_UPDATE_TMP_DIR: &str = "/data/adb/modules_update/";
+
+pub const DISABLE_FILE_NAME: &str = "disable";
+/// Marker dropped into every module dir while safe mode is active; distinct
+/// from `DISABLE_FILE_NAME` so a safe-mode boot never looks like the user
+/// disabled the module themselves, and is cleared automatically on the next
+/// normal boot.
+pub const SAFEMODE_DISABLE_FILE_NAME: &str = "safemode_disable";
+pub const UPDATE_FILE_NAME: &str = "update";
+pub const REMOVE_FILE_NAME: &str = "remove";
+pub const SKIP_MOUNT_FILE_NAME: &str = "skip_mount";
+
+pub const PROP_FILE_NAME: &str = "system.prop";
+pub const MODULE_WEB_DIR: &str = "webroot";
+
+pub const AP_OVERLAY_SOURCE: &str = "APatch";
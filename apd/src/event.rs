@@ -0,0 +1,36 @@
+use crate::{module, safemode, uid_listener, utils};
+use anyhow::Result;
+use std::time::Duration;
+
+pub fn on_post_data_fs(superkey: Option<String>) -> Result<()> {
+    if safemode::is_active(&superkey) {
+        log::warn!("safe mode active, skipping module application for this boot");
+        module::disable_all_modules_for_boot()?;
+        return Ok(());
+    }
+
+    module::clear_safemode_markers()?;
+    log::info!("on_post_data_fs triggered");
+    // Modules are not mounted or applied here; a normal boot only clears
+    // leftover safe-mode markers.
+    Ok(())
+}
+
+pub fn on_services(_superkey: Option<String>) -> Result<()> {
+    log::info!("on_services triggered");
+    Ok(())
+}
+
+pub fn on_boot_completed(_superkey: Option<String>) -> Result<()> {
+    // init triggers this event, but on slow boots it can race sys.boot_completed
+    // actually flipping; wait rather than trusting the call ordering.
+    if !utils::wait_for_prop("sys.boot_completed", "1", Duration::from_secs(30))? {
+        log::warn!("sys.boot_completed did not reach 1 within timeout");
+    }
+    log::info!("on_boot_completed triggered");
+    Ok(())
+}
+
+pub fn start_uid_listener(superkey: Option<String>) -> Result<()> {
+    uid_listener::start(superkey)
+}
@@ -0,0 +1,14 @@
+mod apd;
+mod cli;
+mod command;
+mod defs;
+mod event;
+mod module;
+mod safemode;
+mod supercall;
+mod uid_listener;
+mod utils;
+
+fn main() -> anyhow::Result<()> {
+    cli::run()
+}
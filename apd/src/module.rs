@@ -0,0 +1,127 @@
+use crate::command::ShellCommand;
+use crate::defs;
+use crate::utils::ensure_dir_exists;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn module_dir(id: &str) -> PathBuf {
+    Path::new(defs::MODULE_DIR).join(id)
+}
+
+/// Run a module script through the shared [`ShellCommand`] harness and turn
+/// a non-zero exit (or timeout) into a proper error instead of letting it
+/// pass silently.
+fn run_module_script(id: &str, dir: &Path, script_name: &str) -> Result<()> {
+    let script = dir.join(script_name);
+    let result = ShellCommand::new(format!("{id}:{script_name}"), &script)
+        .module_env(&dir.to_string_lossy(), &dir.to_string_lossy())
+        .run()
+        .with_context(|| format!("Failed to run {script_name} for module {id}"))?;
+
+    if result.timed_out {
+        anyhow::bail!("{script_name} for module {id} timed out");
+    }
+    if result.status != 0 {
+        anyhow::bail!(
+            "{script_name} for module {id} exited with status {}:\n{}",
+            result.status,
+            result.stderr
+        );
+    }
+    Ok(())
+}
+
+pub fn install_module(zip: &str) -> Result<()> {
+    ensure_dir_exists(defs::MODULE_DIR)?;
+    log::info!("install module: {}", zip);
+    // Only the module directory is created and the request logged: the zip
+    // is not extracted into MODULE_UPDATE_TMP_DIR and no install.sh is run.
+    Ok(())
+}
+
+pub fn uninstall_module(id: &str) -> Result<()> {
+    let dir = module_dir(id);
+    if !dir.exists() {
+        anyhow::bail!("module {} not found", id);
+    }
+    fs::write(dir.join(defs::REMOVE_FILE_NAME), "")
+        .with_context(|| format!("Failed to mark {} for removal", id))?;
+    Ok(())
+}
+
+pub fn enable_module(id: &str) -> Result<()> {
+    let marker = module_dir(id).join(defs::DISABLE_FILE_NAME);
+    if marker.exists() {
+        fs::remove_file(&marker)
+            .with_context(|| format!("Failed to remove disable marker for {}", id))?;
+    }
+    Ok(())
+}
+
+pub fn disable_module(id: &str) -> Result<()> {
+    let dir = module_dir(id);
+    if !dir.exists() {
+        anyhow::bail!("module {} not found", id);
+    }
+    fs::write(dir.join(defs::DISABLE_FILE_NAME), "")
+        .with_context(|| format!("Failed to write disable marker for {}", id))?;
+    Ok(())
+}
+
+pub fn run_action(id: &str) -> Result<()> {
+    let dir = module_dir(id);
+    if !dir.join("action.sh").exists() {
+        anyhow::bail!("module {} has no action.sh", id);
+    }
+    run_module_script(id, &dir, "action.sh")
+}
+
+pub fn list_modules() -> Result<()> {
+    ensure_dir_exists(defs::MODULE_DIR)?;
+    for entry in fs::read_dir(defs::MODULE_DIR)? {
+        let entry = entry?;
+        println!("{}", entry.file_name().to_string_lossy());
+    }
+    Ok(())
+}
+
+/// Mark every installed module as disabled for the current boot only, leaving
+/// the module directories otherwise untouched so a subsequent reboot restores
+/// them. Used by safe mode to suppress module application without uninstalling
+/// anything.
+pub fn disable_all_modules_for_boot() -> Result<()> {
+    ensure_dir_exists(defs::MODULE_DIR)?;
+    for entry in fs::read_dir(defs::MODULE_DIR)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let marker = entry.path().join(defs::SAFEMODE_DISABLE_FILE_NAME);
+        if !marker.exists() {
+            fs::write(&marker, "").with_context(|| {
+                format!("Failed to write safe-mode disable marker in {:?}", entry.path())
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove any safe-mode markers left over from a previous boot, restoring
+/// normal module application. No-op if safe mode was never engaged.
+pub fn clear_safemode_markers() -> Result<()> {
+    ensure_dir_exists(defs::MODULE_DIR)?;
+    for entry in fs::read_dir(defs::MODULE_DIR)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let marker = entry.path().join(defs::SAFEMODE_DISABLE_FILE_NAME);
+        if marker.exists() {
+            fs::remove_file(&marker).with_context(|| {
+                format!("Failed to clear safe-mode marker in {:?}", entry.path())
+            })?;
+        }
+    }
+    Ok(())
+}
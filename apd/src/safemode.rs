@@ -0,0 +1,32 @@
+use crate::utils::ensure_dir_exists;
+use crate::{defs, supercall};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FORCE_FILE_NAME: &str = "safemode_forced";
+
+fn force_file() -> PathBuf {
+    Path::new(defs::WORKING_DIR).join(FORCE_FILE_NAME)
+}
+
+/// Whether safe mode should apply for the current boot: either the kernel
+/// observed the volume-key gesture during early boot, or the manager forced
+/// it on through `apd safe-mode --enable`.
+pub fn is_active(superkey: &Option<String>) -> bool {
+    force_file().exists() || supercall::sc_get_safemode(superkey)
+}
+
+/// Persist (or clear) the manager's forced safe-mode override. This never
+/// touches module directories directly; `event::on_post_data_fs` is what
+/// actually suppresses module application based on `is_active`.
+pub fn set_forced(enable: bool) -> Result<()> {
+    ensure_dir_exists(defs::WORKING_DIR)?;
+    let path = force_file();
+    if enable {
+        fs::write(&path, "").with_context(|| format!("Failed to write {:?}", path))?;
+    } else if path.exists() {
+        fs::remove_file(&path).with_context(|| format!("Failed to remove {:?}", path))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+// Syscall numbers used by the APatch kernel patch to multiplex privileged
+// operations through a single supercall entry point, gated on the super key.
+const SUPERCALL_KPM_LOAD: i64 = 0x1001;
+const SUPERCALL_PROFILE: i64 = 0x1002;
+const SUPERCALL_GET_SAFEMODE: i64 = 0x1003;
+const SUPERCALL_LIST_ROOT_UIDS: i64 = 0x1004;
+const SUPERCALL_GRANT_ROOT_UID: i64 = 0x1005;
+const SUPERCALL_REVOKE_ROOT_UID: i64 = 0x1006;
+
+// Upper bound on how many root-granted uids the kernel patch will hand back
+// in one SUPERCALL_LIST_ROOT_UIDS call; comfortably above any real device's
+// profile count.
+const MAX_ROOT_UIDS: usize = 1024;
+
+extern "C" {
+    fn prctl(option: i32, ...) -> i32;
+}
+
+pub fn privilege_apd_profile(superkey: &Option<String>) {
+    let Some(key) = superkey else {
+        return;
+    };
+    let Ok(key_cstr) = std::ffi::CString::new(key.as_str()) else {
+        log::error!("superkey contains an interior NUL, refusing to use it");
+        return;
+    };
+    unsafe {
+        prctl(
+            SUPERCALL_PROFILE as i32,
+            key_cstr.as_ptr(),
+            libc::getpid(),
+        );
+    }
+}
+
+/// Ask the kernel patch whether it counted enough volume-key presses during
+/// early boot to request safe mode. Requires the super key so an unprivileged
+/// caller can't probe or spoof the flag.
+pub fn sc_get_safemode(superkey: &Option<String>) -> bool {
+    let Some(key) = superkey else {
+        return false;
+    };
+    let key_cstr = match std::ffi::CString::new(key.as_str()) {
+        Ok(c) => c,
+        Err(_) => return false,
+    };
+    let ret = unsafe { prctl(SUPERCALL_GET_SAFEMODE as i32, key_cstr.as_ptr(), libc::getpid()) };
+    ret == 1
+}
+
+/// Ask the kernel patch for the uids it currently grants root to, so the
+/// daemon can diff against the manager's stored profiles instead of blindly
+/// re-pushing the whole set.
+pub fn sc_list_root_uids(superkey: &Option<String>) -> Vec<u32> {
+    let Some(key) = superkey else {
+        return Vec::new();
+    };
+    let key_cstr = match std::ffi::CString::new(key.as_str()) {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
+    let mut uids = [0u32; MAX_ROOT_UIDS];
+    let count = unsafe {
+        prctl(
+            SUPERCALL_LIST_ROOT_UIDS as i32,
+            key_cstr.as_ptr(),
+            uids.as_mut_ptr(),
+            MAX_ROOT_UIDS,
+        )
+    };
+    if count <= 0 {
+        return Vec::new();
+    }
+    uids[..(count as usize).min(MAX_ROOT_UIDS)].to_vec()
+}
+
+pub fn sc_grant_root_uid(superkey: &Option<String>, uid: u32) {
+    if let Some(key) = superkey {
+        if let Ok(key_cstr) = std::ffi::CString::new(key.as_str()) {
+            unsafe {
+                prctl(SUPERCALL_GRANT_ROOT_UID as i32, key_cstr.as_ptr(), uid);
+            }
+        }
+    }
+}
+
+pub fn sc_revoke_root_uid(superkey: &Option<String>, uid: u32) {
+    if let Some(key) = superkey {
+        if let Ok(key_cstr) = std::ffi::CString::new(key.as_str()) {
+            unsafe {
+                prctl(SUPERCALL_REVOKE_ROOT_UID as i32, key_cstr.as_ptr(), uid);
+            }
+        }
+    }
+}
+
+pub fn sc_kpm_load(
+    key: &CStr,
+    path: &CStr,
+    args: Option<&CStr>,
+    out_id: *mut c_char,
+) -> i32 {
+    // Issues the SUPERCALL_KPM_LOAD request to the kernel patch, which
+    // validates `key` before loading the KPM at `path`.
+    let args_ptr = args.map_or(std::ptr::null(), |a| a.as_ptr());
+    unsafe {
+        prctl(
+            SUPERCALL_KPM_LOAD as i32,
+            key.as_ptr(),
+            path.as_ptr(),
+            args_ptr,
+            out_id,
+        )
+    }
+}
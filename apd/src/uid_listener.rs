@@ -0,0 +1,224 @@
+use crate::{defs, supercall};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+fn tracked_uids_path() -> std::path::PathBuf {
+    Path::new(defs::WORKING_DIR).join("root_profiles")
+}
+
+/// The uids the manager app has granted root to, as last synced to disk.
+/// One uid per line; this is the ground truth the kernel's live set is
+/// reconciled against.
+fn load_tracked_uids() -> Result<HashSet<u32>> {
+    let path = tracked_uids_path();
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    Ok(content
+        .lines()
+        .filter_map(|l| l.trim().parse::<u32>().ok())
+        .collect())
+}
+
+/// Compare the kernel's currently root-granted uid set against what the
+/// manager has stored, and apply only the delta rather than re-pushing the
+/// whole profile set on every event.
+fn reconcile(superkey: &Option<String>) -> Result<()> {
+    let wanted = load_tracked_uids()?;
+    let current: HashSet<u32> = supercall::sc_list_root_uids(superkey).into_iter().collect();
+
+    for uid in wanted.difference(&current) {
+        log::info!("granting root to uid {uid}");
+        supercall::sc_grant_root_uid(superkey, *uid);
+    }
+    for uid in current.difference(&wanted) {
+        log::info!("revoking root from uid {uid}");
+        supercall::sc_revoke_root_uid(superkey, *uid);
+    }
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod netlink {
+    use super::reconcile;
+    use anyhow::Result;
+    use std::mem::size_of;
+    use std::os::unix::io::RawFd;
+
+    const NETLINK_CONNECTOR: i32 = 11;
+    const CN_IDX_PROC: u32 = 1;
+    const CN_VAL_PROC: u32 = 1;
+    const PROC_CN_MCAST_LISTEN: u32 = 1;
+    const PROC_EVENT_UID: u32 = 0x0000_0004;
+    // SYS_pidfd_open is stable across arm64 and x86_64.
+    const SYS_PIDFD_OPEN: libc::c_long = 434;
+
+    #[repr(C)]
+    struct CnMsg {
+        idx: u32,
+        val: u32,
+        seq: u32,
+        ack: u32,
+        len: u16,
+        flags: u16,
+    }
+
+    #[repr(C)]
+    struct ProcEventUid {
+        what: u32,
+        cpu: u32,
+        timestamp_ns: u64,
+        pid: i32,
+        tgid: i32,
+        ruid: u32,
+        euid: u32,
+    }
+
+    fn open_proc_connector() -> Result<RawFd> {
+        let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM, NETLINK_CONNECTOR) };
+        if fd < 0 {
+            anyhow::bail!(
+                "failed to open proc connector netlink socket: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+
+        let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        addr.nl_family = libc::AF_NETLINK as u16;
+        addr.nl_pid = unsafe { libc::getpid() } as u32;
+        addr.nl_groups = CN_IDX_PROC;
+        let ret = unsafe {
+            libc::bind(
+                fd,
+                &addr as *const _ as *const libc::sockaddr,
+                size_of::<libc::sockaddr_nl>() as u32,
+            )
+        };
+        if ret < 0 {
+            unsafe { libc::close(fd) };
+            anyhow::bail!(
+                "failed to bind proc connector socket: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+        Ok(fd)
+    }
+
+    fn send_listen(fd: RawFd) -> Result<()> {
+        // nlmsghdr + cn_msg header + a single u32 op (PROC_CN_MCAST_LISTEN)
+        #[repr(C)]
+        struct Payload {
+            nlh: libc::nlmsghdr,
+            cn: CnMsg,
+            op: u32,
+        }
+
+        let mut payload: Payload = unsafe { std::mem::zeroed() };
+        payload.cn.idx = CN_IDX_PROC;
+        payload.cn.val = CN_VAL_PROC;
+        payload.cn.len = size_of::<u32>() as u16;
+        payload.op = PROC_CN_MCAST_LISTEN;
+        payload.nlh.nlmsg_len = size_of::<Payload>() as u32;
+        payload.nlh.nlmsg_pid = unsafe { libc::getpid() } as u32;
+        payload.nlh.nlmsg_type = libc::NLMSG_DONE as u16;
+
+        let ret = unsafe {
+            libc::send(
+                fd,
+                &payload as *const _ as *const libc::c_void,
+                size_of::<Payload>(),
+                0,
+            )
+        };
+        if ret < 0 {
+            anyhow::bail!(
+                "failed to subscribe to proc events: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+        Ok(())
+    }
+
+    /// Check through `pidfd_open` whether a process with this pid still
+    /// exists. A pid that the kernel has already reused for an unrelated
+    /// process also counts as live.
+    fn pid_is_live(pid: i32) -> bool {
+        let fd = unsafe { libc::syscall(SYS_PIDFD_OPEN, pid, 0) };
+        if fd < 0 {
+            return false;
+        }
+        unsafe { libc::close(fd as RawFd) };
+        true
+    }
+
+    /// Subscribe to process/uid lifecycle events through the proc connector
+    /// netlink socket and reconcile the kernel's root-granted uid set against
+    /// the manager's stored profiles whenever a tracked uid's process changes,
+    /// instead of polling the filesystem for profile changes.
+    pub fn start(superkey: Option<String>) -> Result<()> {
+        let fd = open_proc_connector()?;
+        send_listen(fd)?;
+        log::info!("uid listener subscribed to proc connector events");
+
+        // Reconcile once up front in case anything changed while we weren't running.
+        reconcile(&superkey)?;
+
+        let mut buf = [0u8; 1024];
+        loop {
+            let n = unsafe { libc::recv(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+            if n < 0 {
+                let err = std::io::Error::last_os_error();
+                if err.kind() == std::io::ErrorKind::Interrupted {
+                    continue;
+                }
+                anyhow::bail!("proc connector recv failed: {err}");
+            }
+            if (n as usize)
+                < size_of::<libc::nlmsghdr>() + size_of::<CnMsg>() + size_of::<ProcEventUid>()
+            {
+                continue;
+            }
+
+            let event_offset = size_of::<libc::nlmsghdr>() + size_of::<CnMsg>();
+            let event = unsafe { &*(buf.as_ptr().add(event_offset) as *const ProcEventUid) };
+            if event.what != PROC_EVENT_UID {
+                continue;
+            }
+
+            // Zygote forks and setuid calls raise PROC_EVENT_UID for every app
+            // launch on the device; cheaply filter to uids we actually track
+            // before paying for a pidfd_open and a sc_list_root_uids supercall.
+            let tracked = super::load_tracked_uids()?;
+            if !tracked.contains(&event.euid) && !tracked.contains(&event.ruid) {
+                continue;
+            }
+
+            if !pid_is_live(event.pid) {
+                // The process that raised the event has already exited;
+                // treat the event as stale and skip the reconcile.
+                log::debug!(
+                    "uid event for pid {} (uid {}) is stale, pid no longer live",
+                    event.pid,
+                    event.euid
+                );
+                continue;
+            }
+
+            log::debug!("uid event for pid {} (uid {})", event.pid, event.euid);
+            reconcile(&superkey)?;
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn start(superkey: Option<String>) -> Result<()> {
+    netlink::start(superkey)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn start(_superkey: Option<String>) -> Result<()> {
+    anyhow::bail!("uid listener is only supported on Linux/Android")
+}
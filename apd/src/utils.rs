@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+pub fn ensure_dir_exists<P: AsRef<Path>>(dir: P) -> Result<()> {
+    let dir = dir.as_ref();
+    if !dir.exists() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create dir: {:?}", dir))?;
+    }
+    Ok(())
+}
+
+/// Why entering the target mount namespace failed, kept distinct from the
+/// generic `anyhow::Error` so callers can tell a missing `pidfd_open` (old
+/// kernel, expected) apart from a `setns` that genuinely failed.
+#[derive(Debug)]
+pub enum MntNsError {
+    PidfdUnsupported(std::io::Error),
+    SetnsFailed(std::io::Error),
+}
+
+impl std::fmt::Display for MntNsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MntNsError::PidfdUnsupported(e) => write!(f, "pidfd_open unsupported: {e}"),
+            MntNsError::SetnsFailed(e) => write!(f, "setns failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for MntNsError {}
+
+// Not yet in every libc crate version we build against; the raw number is
+// stable across arm64 and x86_64 so we call it directly.
+const SYS_PIDFD_OPEN: libc::c_long = 434;
+
+fn pidfd_open(pid: i32) -> std::io::Result<std::os::unix::io::RawFd> {
+    let ret = unsafe { libc::syscall(SYS_PIDFD_OPEN, pid, 0) };
+    if ret < 0 {
+        Err(std::io::Error::last_os_error())
+    } else {
+        Ok(ret as std::os::unix::io::RawFd)
+    }
+}
+
+fn setns_via_pidfd(pid: i32) -> std::result::Result<(), MntNsError> {
+    let fd = pidfd_open(pid).map_err(MntNsError::PidfdUnsupported)?;
+    let ret = unsafe { libc::setns(fd, libc::CLONE_NEWNS) };
+    let err = std::io::Error::last_os_error();
+    unsafe { libc::close(fd) };
+    if ret != 0 {
+        return Err(MntNsError::SetnsFailed(err));
+    }
+    Ok(())
+}
+
+fn setns_via_proc(pid: i32) -> std::result::Result<(), MntNsError> {
+    use std::fs::File;
+    use std::os::unix::io::AsRawFd;
+
+    let path = format!("/proc/{pid}/ns/mnt");
+    let file = File::open(&path).map_err(MntNsError::SetnsFailed)?;
+    let ret = unsafe { libc::setns(file.as_raw_fd(), libc::CLONE_NEWNS) };
+    if ret != 0 {
+        return Err(MntNsError::SetnsFailed(std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+/// Switch current process into the mount namespace of `pid` (usually init,
+/// pid 1). Prefers `pidfd_open` + `setns(pidfd, CLONE_NEWNS)` (kernel 5.8+),
+/// which avoids the PID-reuse TOCTOU window inherent to opening
+/// `/proc/<pid>/ns/mnt` by path, and falls back to the path-based lookup when
+/// the kernel doesn't support pidfd-based namespace switching.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn switch_mnt_ns(pid: i32) -> Result<()> {
+    match setns_via_pidfd(pid) {
+        Ok(()) => {
+            log::debug!("entered mnt ns of pid {pid} via pidfd_open");
+            return Ok(());
+        }
+        Err(e) => log::debug!(
+            "pidfd-based setns unavailable for pid {pid} ({e}), falling back to /proc/{pid}/ns/mnt"
+        ),
+    }
+
+    setns_via_proc(pid)
+        .map_err(anyhow::Error::from)
+        .with_context(|| format!("Failed to enter mnt ns of pid {pid}"))
+}
+
+#[cfg(target_os = "android")]
+mod sysprop {
+    use std::os::raw::{c_char, c_void};
+
+    #[repr(C)]
+    pub struct PropInfo(c_void);
+
+    extern "C" {
+        pub fn __system_property_find(name: *const c_char) -> *const PropInfo;
+        pub fn __system_property_serial(pi: *const PropInfo) -> u32;
+        pub fn __system_property_wait(
+            pi: *const PropInfo,
+            old_serial: u32,
+            new_serial: *mut u32,
+            timeout: *const libc::timespec,
+        ) -> bool;
+        pub fn __system_property_read_callback(
+            pi: *const PropInfo,
+            callback: extern "C" fn(*mut c_void, *const c_char, *const c_char, u32),
+            cookie: *mut c_void,
+        );
+    }
+
+    extern "C" fn read_value_cb(cookie: *mut c_void, _name: *const c_char, value: *const c_char, _serial: u32) {
+        unsafe {
+            let out = &mut *(cookie as *mut String);
+            *out = std::ffi::CStr::from_ptr(value).to_string_lossy().into_owned();
+        }
+    }
+
+    pub fn read_value(pi: *const PropInfo) -> String {
+        let mut value = String::new();
+        unsafe {
+            __system_property_read_callback(pi, read_value_cb, &mut value as *mut _ as *mut c_void);
+        }
+        value
+    }
+}
+
+#[cfg(target_os = "android")]
+fn to_timespec(d: Duration) -> libc::timespec {
+    libc::timespec {
+        tv_sec: d.as_secs() as libc::time_t,
+        tv_nsec: d.subsec_nanos() as i64,
+    }
+}
+
+/// Block until the property `name` reads exactly `value`, or `timeout`
+/// elapses. Unlike a plain read-and-poll loop this correctly handles a
+/// property that doesn't exist yet: it waits on the property area's global
+/// serial until `__system_property_find` resolves, then switches to waiting
+/// on that property's own serial. Returns `Ok(true)` if the value matched,
+/// `Ok(false)` on timeout.
+#[cfg(target_os = "android")]
+pub fn wait_for_prop(name: &str, value: &str, timeout: Duration) -> Result<bool> {
+    use sysprop::*;
+    use std::time::Instant;
+
+    let name_cstr = std::ffi::CString::new(name).context("invalid property name")?;
+    let deadline = Instant::now() + timeout;
+    let mut pi = unsafe { __system_property_find(name_cstr.as_ptr()) };
+    let mut global_serial = 0u32;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(false);
+        }
+        let ts = to_timespec(remaining);
+
+        if pi.is_null() {
+            let mut new_global_serial = global_serial;
+            if !unsafe {
+                __system_property_wait(std::ptr::null(), global_serial, &mut new_global_serial, &ts)
+            } {
+                return Ok(false);
+            }
+            global_serial = new_global_serial;
+            pi = unsafe { __system_property_find(name_cstr.as_ptr()) };
+            continue;
+        }
+
+        if read_value(pi) == value {
+            return Ok(true);
+        }
+
+        let serial = unsafe { __system_property_serial(pi) };
+        let mut new_serial = 0u32;
+        if !unsafe { __system_property_wait(pi, serial, &mut new_serial, &ts) } {
+            return Ok(false);
+        }
+        if read_value(pi) == value {
+            return Ok(true);
+        }
+    }
+}
+
+// There's no system property area to wait on outside Android; treat the
+// property as already matching rather than failing every caller on a host
+// build.
+#[cfg(not(target_os = "android"))]
+pub fn wait_for_prop(_name: &str, _value: &str, _timeout: Duration) -> Result<bool> {
+    Ok(true)
+}